@@ -9,6 +9,48 @@ use solana_program::{
     rent::Rent,
     sysvar::Sysvar,
 };
+use thiserror::Error;
+
+// NFT程序自定义错误
+#[derive(Error, Debug, Copy, Clone)]
+pub enum NFTError {
+    #[error("name exceeds 32 bytes")]
+    NameTooLong,
+    #[error("symbol exceeds 10 bytes")]
+    SymbolTooLong,
+    #[error("uri exceeds 200 bytes")]
+    UriTooLong,
+    #[error("seller fee basis points exceeds 10000")]
+    InvalidSellerFeeBasisPoints,
+    #[error("a maximum of 5 creators is allowed")]
+    TooManyCreators,
+    #[error("creator shares must sum to exactly 100")]
+    InvalidCreatorShares,
+    #[error("a creator can only be marked verified if it signed the instruction")]
+    UnverifiedCreator,
+    #[error("master edition max supply reached")]
+    MaxSupplyReached,
+    #[error("this edition number has already been printed")]
+    EditionAlreadyPrinted,
+    #[error("master edition has already been created for this NFT")]
+    MasterEditionAlreadyInitialized,
+    #[error("edition marker account does not match the derived PDA for this edition's page")]
+    InvalidEditionMarker,
+}
+
+impl From<NFTError> for ProgramError {
+    fn from(e: NFTError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+// 版税接收者,对应Metaplex的Creator
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
 
 // 定义NFT元数据结构
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
@@ -16,11 +58,69 @@ pub struct NFTMetadata {
     pub name: String,
     pub symbol: String,
     pub uri: String,
-    pub creator: Pubkey,
-    pub royalty_percentage: u8,
+    pub owner: Pubkey,
+    pub creators: Vec<Creator>,
+    pub seller_fee_basis_points: u16,
     pub is_mutable: bool,
 }
 
+// 母版信息:记录已打印的复刻数量以及上限(`None`表示不限量)
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct MasterEdition {
+    pub is_initialized: bool,
+    pub supply: u64,
+    pub max_supply: Option<u64>,
+}
+
+// 每个位图页覆盖的复刻编号数量,与Metaplex的`edition_marker`一致
+pub const EDITION_MARKER_BIT_SIZE: u64 = 248;
+const EDITION_MARKER_BYTES: usize = (EDITION_MARKER_BIT_SIZE as usize + 7) / 8;
+
+// 位图账户PDA的种子前缀,账户地址由母版NFT账户公钥与`edition / EDITION_MARKER_BIT_SIZE`派生而来,
+// 防止调用者传入任意账户伪造或绕过已打印记录
+const EDITION_MARKER_SEED: &[u8] = b"edition_marker";
+
+// 复刻编号位图:`edition_number % EDITION_MARKER_BIT_SIZE`对应的位记录该编号是否已被打印,
+// 一个位图账户覆盖`[page * EDITION_MARKER_BIT_SIZE, (page + 1) * EDITION_MARKER_BIT_SIZE)`区间
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct EditionMarker {
+    pub ledger: [u8; EDITION_MARKER_BYTES],
+}
+
+impl Default for EditionMarker {
+    fn default() -> Self {
+        EditionMarker {
+            ledger: [0u8; EDITION_MARKER_BYTES],
+        }
+    }
+}
+
+impl EditionMarker {
+    fn is_set(&self, bit: u64) -> bool {
+        let byte = bit / 8;
+        let mask = 1u8 << (bit % 8);
+        self.ledger[byte as usize] & mask != 0
+    }
+
+    fn set(&mut self, bit: u64) {
+        let byte = bit / 8;
+        let mask = 1u8 << (bit % 8);
+        self.ledger[byte as usize] |= mask;
+    }
+}
+
+// 由母版NFT复刻出的编号版NFT
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct PrintedEdition {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub owner: Pubkey,
+    pub creators: Vec<Creator>,
+    pub seller_fee_basis_points: u16,
+    pub edition: u64,
+}
+
 // 定义NFT指令
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum NFTInstruction {
@@ -29,7 +129,8 @@ pub enum NFTInstruction {
         name: String,
         symbol: String,
         uri: String,
-        royalty_percentage: u8,
+        creators: Vec<Creator>,
+        seller_fee_basis_points: u16,
         is_mutable: bool,
     },
     // 转移NFT
@@ -42,6 +143,14 @@ pub enum NFTInstruction {
         symbol: Option<String>,
         uri: Option<String>,
     },
+    // 将NFT指定为母版,允许后续打印有限数量的复刻
+    CreateMasterEdition {
+        max_supply: Option<u64>,
+    },
+    // 从母版NFT打印一个编号复刻
+    PrintEdition {
+        edition: u64,
+    },
 }
 
 // 程序入口点
@@ -61,7 +170,8 @@ pub fn process_instruction(
             name,
             symbol,
             uri,
-            royalty_percentage,
+            creators,
+            seller_fee_basis_points,
             is_mutable,
         } => {
             msg!("Instruction: CreateNFT");
@@ -71,7 +181,8 @@ pub fn process_instruction(
                 name,
                 symbol,
                 uri,
-                royalty_percentage,
+                creators,
+                seller_fee_basis_points,
                 is_mutable,
             )
         }
@@ -83,9 +194,56 @@ pub fn process_instruction(
             msg!("Instruction: UpdateMetadata");
             process_update_metadata(program_id, accounts, name, symbol, uri)
         }
+        NFTInstruction::CreateMasterEdition { max_supply } => {
+            msg!("Instruction: CreateMasterEdition");
+            process_create_master_edition(program_id, accounts, max_supply)
+        }
+        NFTInstruction::PrintEdition { edition } => {
+            msg!("Instruction: PrintEdition");
+            process_print_edition(program_id, accounts, edition)
+        }
     }
 }
 
+// 校验元数据字段以及版税创建者列表是否合法
+fn validate_metadata(
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    creators: &[Creator],
+    seller_fee_basis_points: u16,
+    signer_keys: &[&Pubkey],
+) -> Result<(), NFTError> {
+    if name.len() > 32 {
+        return Err(NFTError::NameTooLong);
+    }
+    if symbol.len() > 10 {
+        return Err(NFTError::SymbolTooLong);
+    }
+    if uri.len() > 200 {
+        return Err(NFTError::UriTooLong);
+    }
+    if seller_fee_basis_points > 10000 {
+        return Err(NFTError::InvalidSellerFeeBasisPoints);
+    }
+    if creators.len() > 5 {
+        return Err(NFTError::TooManyCreators);
+    }
+
+    let total_share: u16 = creators.iter().map(|c| c.share as u16).sum();
+    if total_share != 100 {
+        return Err(NFTError::InvalidCreatorShares);
+    }
+
+    for creator in creators {
+        if creator.verified && !signer_keys.contains(&&creator.address) {
+            return Err(NFTError::UnverifiedCreator);
+        }
+    }
+
+    Ok(())
+}
+
 // 创建NFT
 fn process_create_nft(
     program_id: &Pubkey,
@@ -93,11 +251,12 @@ fn process_create_nft(
     name: String,
     symbol: String,
     uri: String,
-    royalty_percentage: u8,
+    creators: Vec<Creator>,
+    seller_fee_basis_points: u16,
     is_mutable: bool,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    
+
     // 获取账户信息
     let nft_account = next_account_info(account_info_iter)?;
     let creator_account = next_account_info(account_info_iter)?;
@@ -119,18 +278,30 @@ fn process_create_nft(
         return Err(ProgramError::AccountNotRentExempt);
     }
 
-    // 检查版税百分比是否有效 (0-100)
-    if royalty_percentage > 100 {
-        return Err(ProgramError::InvalidArgument);
-    }
+    // 收集本次指令中实际签名的账户,用于校验`verified`创建者
+    let signer_keys: Vec<&Pubkey> = accounts
+        .iter()
+        .filter(|a| a.is_signer)
+        .map(|a| a.key)
+        .collect();
+
+    validate_metadata(
+        &name,
+        &symbol,
+        &uri,
+        &creators,
+        seller_fee_basis_points,
+        &signer_keys,
+    )?;
 
     // 创建NFT元数据
     let nft_metadata = NFTMetadata {
         name,
         symbol,
         uri,
-        creator: *creator_account.key,
-        royalty_percentage,
+        owner: *creator_account.key,
+        creators,
+        seller_fee_basis_points,
         is_mutable,
     };
 
@@ -148,7 +319,7 @@ fn process_transfer_nft(
     new_owner: Pubkey,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    
+
     // 获取账户信息
     let nft_account = next_account_info(account_info_iter)?;
     let current_owner = next_account_info(account_info_iter)?;
@@ -163,7 +334,7 @@ fn process_transfer_nft(
     let mut nft_metadata = NFTMetadata::try_from_slice(&nft_account.data.borrow())?;
 
     // 验证当前所有者
-    if nft_metadata.creator != *current_owner.key {
+    if nft_metadata.owner != *current_owner.key {
         return Err(ProgramError::InvalidAccountData);
     }
 
@@ -178,7 +349,7 @@ fn process_transfer_nft(
     }
 
     // 更新所有者
-    nft_metadata.creator = new_owner;
+    nft_metadata.owner = new_owner;
 
     // 序列化并存储更新后的元数据
     nft_metadata.serialize(&mut &mut nft_account.data.borrow_mut()[..])?;
@@ -196,7 +367,7 @@ fn process_update_metadata(
     uri: Option<String>,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    
+
     // 获取账户信息
     let nft_account = next_account_info(account_info_iter)?;
     let owner_account = next_account_info(account_info_iter)?;
@@ -215,7 +386,7 @@ fn process_update_metadata(
     }
 
     // 验证所有者
-    if nft_metadata.creator != *owner_account.key {
+    if nft_metadata.owner != *owner_account.key {
         return Err(ProgramError::InvalidAccountData);
     }
 
@@ -225,19 +396,175 @@ fn process_update_metadata(
     }
 
     // 更新元数据
-    if let Some(new_name) = name {
-        nft_metadata.name = new_name;
-    }
-    if let Some(new_symbol) = symbol {
-        nft_metadata.symbol = new_symbol;
-    }
-    if let Some(new_uri) = uri {
-        nft_metadata.uri = new_uri;
-    }
+    let new_name = name.unwrap_or_else(|| nft_metadata.name.clone());
+    let new_symbol = symbol.unwrap_or_else(|| nft_metadata.symbol.clone());
+    let new_uri = uri.unwrap_or_else(|| nft_metadata.uri.clone());
+
+    let signer_keys: Vec<&Pubkey> = accounts
+        .iter()
+        .filter(|a| a.is_signer)
+        .map(|a| a.key)
+        .collect();
+
+    validate_metadata(
+        &new_name,
+        &new_symbol,
+        &new_uri,
+        &nft_metadata.creators,
+        nft_metadata.seller_fee_basis_points,
+        &signer_keys,
+    )?;
+
+    nft_metadata.name = new_name;
+    nft_metadata.symbol = new_symbol;
+    nft_metadata.uri = new_uri;
 
     // 序列化并存储更新后的元数据
     nft_metadata.serialize(&mut &mut nft_account.data.borrow_mut()[..])?;
 
     msg!("NFT元数据更新成功");
     Ok(())
-} 
\ No newline at end of file
+}
+
+// 将一个已存在的NFT设置为母版,使其可以打印有限数量(或不限量)的编号复刻
+fn process_create_master_edition(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_supply: Option<u64>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let nft_account = next_account_info(account_info_iter)?;
+    let master_edition_account = next_account_info(account_info_iter)?;
+    let owner_account = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+
+    if nft_account.owner != program_id || master_edition_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let nft_metadata = NFTMetadata::try_from_slice(&nft_account.data.borrow())?;
+
+    if nft_metadata.owner != *owner_account.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !owner_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let rent = &Rent::from_account_info(rent_account)?;
+    if !rent.is_exempt(master_edition_account.lamports(), master_edition_account.data_len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+
+    let existing = MasterEdition::try_from_slice(&master_edition_account.data.borrow())?;
+    if existing.is_initialized {
+        return Err(NFTError::MasterEditionAlreadyInitialized.into());
+    }
+
+    let master_edition = MasterEdition {
+        is_initialized: true,
+        supply: 0,
+        max_supply,
+    };
+
+    master_edition.serialize(&mut &mut master_edition_account.data.borrow_mut()[..])?;
+
+    msg!("母版创建成功");
+    Ok(())
+}
+
+// 从母版NFT打印一个编号复刻,复刻编号在位图账户中登记以防重复打印
+fn process_print_edition(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    edition: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let master_nft_account = next_account_info(account_info_iter)?;
+    let master_edition_account = next_account_info(account_info_iter)?;
+    let edition_marker_account = next_account_info(account_info_iter)?;
+    let new_edition_account = next_account_info(account_info_iter)?;
+    let owner_account = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+
+    if master_nft_account.owner != program_id
+        || master_edition_account.owner != program_id
+        || edition_marker_account.owner != program_id
+        || new_edition_account.owner != program_id
+    {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let master_metadata = NFTMetadata::try_from_slice(&master_nft_account.data.borrow())?;
+
+    if master_metadata.owner != *owner_account.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !owner_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let rent = &Rent::from_account_info(rent_account)?;
+    if !rent.is_exempt(new_edition_account.lamports(), new_edition_account.data_len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+
+    let mut master_edition_data = master_edition_account.data.borrow_mut();
+    let mut master_edition = MasterEdition::try_from_slice(&master_edition_data)?;
+
+    if let Some(max_supply) = master_edition.max_supply {
+        if master_edition.supply >= max_supply {
+            return Err(NFTError::MaxSupplyReached.into());
+        }
+    }
+
+    let page = edition / EDITION_MARKER_BIT_SIZE;
+    let bit = edition % EDITION_MARKER_BIT_SIZE;
+
+    // The marker account must be the PDA for this master NFT's page, not just any
+    // program-owned account, or a caller could pass a fresh marker on every call and
+    // print the same edition number unlimited times
+    let (expected_marker, _bump) = Pubkey::find_program_address(
+        &[
+            EDITION_MARKER_SEED,
+            master_nft_account.key.as_ref(),
+            &page.to_le_bytes(),
+        ],
+        program_id,
+    );
+    if expected_marker != *edition_marker_account.key {
+        return Err(NFTError::InvalidEditionMarker.into());
+    }
+
+    let mut marker_data = edition_marker_account.data.borrow_mut();
+    let mut marker = EditionMarker::try_from_slice(&marker_data)?;
+
+    if marker.is_set(bit) {
+        return Err(NFTError::EditionAlreadyPrinted.into());
+    }
+    marker.set(bit);
+
+    let printed_edition = PrintedEdition {
+        name: master_metadata.name,
+        symbol: master_metadata.symbol,
+        uri: master_metadata.uri,
+        owner: *owner_account.key,
+        creators: master_metadata.creators,
+        seller_fee_basis_points: master_metadata.seller_fee_basis_points,
+        edition,
+    };
+
+    master_edition.supply = master_edition
+        .supply
+        .checked_add(1)
+        .ok_or(ProgramError::InvalidArgument)?;
+
+    printed_edition.serialize(&mut &mut new_edition_account.data.borrow_mut()[..])?;
+    marker.serialize(&mut &mut marker_data[..])?;
+    master_edition.serialize(&mut &mut master_edition_data[..])?;
+
+    msg!("复刻 #{} 打印成功", edition);
+    Ok(())
+}
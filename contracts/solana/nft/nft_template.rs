@@ -3,37 +3,76 @@
 
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Transfer};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{
+    self, Mint as Mint2022, TokenAccount as TokenAccount2022, TokenMetadataInitialize,
+};
 use mpl_token_metadata::instruction as mpl_instruction;
+use mpl_token_metadata::state::TokenMetadataAccount;
 use solana_program::program::{invoke, invoke_signed};
+use solana_program::system_instruction;
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"); // Replace with actual program ID
 
+/// Splits `sale_price` into a total royalty amount (per `seller_fee_basis_points`) and each
+/// creator's share of it, in the same order as `creators`. Fails if the shares don't sum to 100.
+fn calculate_royalty_payouts(
+    sale_price: u64,
+    seller_fee_basis_points: u16,
+    creators: &[mpl_token_metadata::state::Creator],
+) -> Result<(u64, Vec<u64>)> {
+    let royalty_amount = (sale_price as u128)
+        .checked_mul(seller_fee_basis_points as u128)
+        .ok_or(ErrorCode::RoyaltyOverflow)?
+        .checked_div(10_000)
+        .ok_or(ErrorCode::RoyaltyOverflow)? as u64;
+
+    let mut share_total: u16 = 0;
+    let mut payouts = Vec::with_capacity(creators.len());
+    for creator in creators {
+        share_total += creator.share as u16;
+
+        let payout = (royalty_amount as u128)
+            .checked_mul(creator.share as u128)
+            .ok_or(ErrorCode::RoyaltyOverflow)?
+            .checked_div(100)
+            .ok_or(ErrorCode::RoyaltyOverflow)? as u64;
+        payouts.push(payout);
+    }
+
+    require_eq!(share_total, 100, ErrorCode::InvalidCreatorShares);
+
+    Ok((royalty_amount, payouts))
+}
+
 #[program]
 pub mod nft_program {
     use super::*;
 
-    /// Create a new NFT
+    /// Create a new NFT, optionally tagging it as an unverified member of a collection
     pub fn create_nft(
         ctx: Context<CreateNFT>,
         name: String,
         symbol: String,
         uri: String,
         seller_fee_basis_points: u16,
+        collection: Option<Pubkey>,
+        max_supply: Option<u64>,
     ) -> Result<()> {
         msg!("Creating new NFT: {}", name);
-        
+
         // Mint 1 token (NFT is a token with supply of 1)
         let cpi_accounts = MintTo {
             mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.token_account.to_account_info(),
             authority: ctx.accounts.authority.to_account_info(),
         };
-        
+
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        
+
         token::mint_to(cpi_ctx, 1)?;
-        
+
         // Create Metadata account
         let creator = vec![
             mpl_token_metadata::state::Creator {
@@ -43,6 +82,13 @@ pub mod nft_program {
             }
         ];
 
+        // An NFT can reference a collection mint up front; the membership starts
+        // unverified until a follow-up `verify_collection` call CPIs into Metaplex
+        let collection_data = collection.map(|key| mpl_token_metadata::state::Collection {
+            verified: false,
+            key,
+        });
+
         invoke(
             &mpl_instruction::create_metadata_accounts_v3(
                 ctx.accounts.token_metadata_program.key(),
@@ -58,7 +104,7 @@ pub mod nft_program {
                 seller_fee_basis_points,
                 true,
                 true,
-                None,
+                collection_data,
                 None,
                 None,
             ),
@@ -83,7 +129,7 @@ pub mod nft_program {
                 ctx.accounts.authority.key(),
                 ctx.accounts.metadata.key(),
                 ctx.accounts.payer.key(),
-                Some(0), // Max supply of 0 means non-fungible
+                max_supply, // None/Some(n) allows unlimited/n prints via print_edition
             ),
             &[
                 ctx.accounts.master_edition.to_account_info(),
@@ -97,9 +143,183 @@ pub mod nft_program {
                 ctx.accounts.rent.to_account_info(),
             ],
         )?;
-        
+
         msg!("NFT created successfully");
-        
+
+        Ok(())
+    }
+
+    /// Create a new collection NFT that other NFTs can be grouped under and verified into
+    pub fn create_collection(
+        ctx: Context<CreateCollection>,
+        name: String,
+        symbol: String,
+        uri: String,
+        seller_fee_basis_points: u16,
+        max_supply: Option<u64>,
+    ) -> Result<()> {
+        msg!("Creating new collection NFT: {}", name);
+
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.token_account.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::mint_to(cpi_ctx, 1)?;
+
+        let creator = vec![
+            mpl_token_metadata::state::Creator {
+                address: ctx.accounts.authority.key(),
+                verified: true,
+                share: 100,
+            }
+        ];
+
+        // `CollectionDetails::V1 { size }` marks this mint as a sized collection,
+        // letting Metaplex track how many items have been verified into it
+        invoke(
+            &mpl_instruction::create_metadata_accounts_v3(
+                ctx.accounts.token_metadata_program.key(),
+                ctx.accounts.metadata.key(),
+                ctx.accounts.mint.key(),
+                ctx.accounts.authority.key(),
+                ctx.accounts.payer.key(),
+                ctx.accounts.authority.key(),
+                name,
+                symbol,
+                uri,
+                Some(creator),
+                seller_fee_basis_points,
+                true,
+                true,
+                None,
+                None,
+                Some(mpl_token_metadata::state::CollectionDetails::V1 { size: 0 }),
+            ),
+            &[
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+            ],
+        )?;
+
+        invoke(
+            &mpl_instruction::create_master_edition_v3(
+                ctx.accounts.token_metadata_program.key(),
+                ctx.accounts.master_edition.key(),
+                ctx.accounts.mint.key(),
+                ctx.accounts.authority.key(),
+                ctx.accounts.authority.key(),
+                ctx.accounts.metadata.key(),
+                ctx.accounts.payer.key(),
+                max_supply,
+            ),
+            &[
+                ctx.accounts.master_edition.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+            ],
+        )?;
+
+        msg!("Collection NFT created successfully");
+
+        Ok(())
+    }
+
+    /// Set an NFT's collection and verify membership in a single call
+    pub fn set_and_verify_collection(ctx: Context<VerifyCollection>) -> Result<()> {
+        invoke(
+            &mpl_instruction::set_and_verify_collection(
+                ctx.accounts.token_metadata_program.key(),
+                ctx.accounts.metadata.key(),
+                ctx.accounts.collection_authority.key(),
+                ctx.accounts.payer.key(),
+                ctx.accounts.collection_authority.key(),
+                ctx.accounts.collection_mint.key(),
+                ctx.accounts.collection_metadata.key(),
+                ctx.accounts.collection_master_edition.key(),
+                None,
+            ),
+            &[
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.collection_authority.to_account_info(),
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.collection_mint.to_account_info(),
+                ctx.accounts.collection_metadata.to_account_info(),
+                ctx.accounts.collection_master_edition.to_account_info(),
+            ],
+        )?;
+
+        msg!("NFT set and verified as collection member");
+
+        Ok(())
+    }
+
+    /// Verify that an NFT minted with a `collection` reference truly belongs to it
+    pub fn verify_collection(ctx: Context<VerifyCollection>) -> Result<()> {
+        invoke(
+            &mpl_instruction::verify_sized_collection_item(
+                ctx.accounts.token_metadata_program.key(),
+                ctx.accounts.metadata.key(),
+                ctx.accounts.collection_authority.key(),
+                ctx.accounts.payer.key(),
+                ctx.accounts.collection_mint.key(),
+                ctx.accounts.collection_metadata.key(),
+                ctx.accounts.collection_master_edition.key(),
+                None,
+            ),
+            &[
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.collection_authority.to_account_info(),
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.collection_mint.to_account_info(),
+                ctx.accounts.collection_metadata.to_account_info(),
+                ctx.accounts.collection_master_edition.to_account_info(),
+            ],
+        )?;
+
+        msg!("NFT verified as collection member");
+
+        Ok(())
+    }
+
+    /// Unverify an NFT's collection membership
+    pub fn unverify_collection(ctx: Context<VerifyCollection>) -> Result<()> {
+        invoke(
+            &mpl_instruction::unverify_collection(
+                ctx.accounts.token_metadata_program.key(),
+                ctx.accounts.metadata.key(),
+                ctx.accounts.collection_authority.key(),
+                ctx.accounts.collection_mint.key(),
+                ctx.accounts.collection_metadata.key(),
+                ctx.accounts.collection_master_edition.key(),
+                None,
+            ),
+            &[
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.collection_authority.to_account_info(),
+                ctx.accounts.collection_mint.to_account_info(),
+                ctx.accounts.collection_metadata.to_account_info(),
+                ctx.accounts.collection_master_edition.to_account_info(),
+            ],
+        )?;
+
+        msg!("NFT collection membership unverified");
+
         Ok(())
     }
 
@@ -123,19 +343,22 @@ pub mod nft_program {
         Ok(())
     }
 
-    /// Update NFT metadata
+    /// Update NFT metadata, changing only the fields the caller explicitly supplies and
+    /// leaving everything else (symbol, royalties, creators, collection, uses) untouched
     pub fn update_metadata(
         ctx: Context<UpdateMetadata>,
         new_uri: String,
         new_name: Option<String>,
+        new_symbol: Option<String>,
+        new_seller_fee_basis_points: Option<u16>,
+        new_creators: Option<Vec<mpl_token_metadata::state::Creator>>,
     ) -> Result<()> {
-        // Get existing metadata
-        let current_name = if let Some(name) = new_name {
-            name
-        } else {
-            "".to_string() // Will keep current name
-        };
-        
+        // Read the metadata account's current state so unchanged fields can be carried forward
+        let current_metadata = mpl_token_metadata::state::Metadata::from_account_info(
+            &ctx.accounts.metadata.to_account_info(),
+        )?;
+        let current_data = current_metadata.data;
+
         invoke(
             &mpl_instruction::update_metadata_accounts_v2(
                 ctx.accounts.token_metadata_program.key(),
@@ -143,13 +366,14 @@ pub mod nft_program {
                 ctx.accounts.authority.key(),
                 None,
                 Some(mpl_token_metadata::state::DataV2 {
-                    name: current_name,
-                    symbol: "".to_string(), // Keep current symbol
+                    name: new_name.unwrap_or(current_data.name),
+                    symbol: new_symbol.unwrap_or(current_data.symbol),
                     uri: new_uri,
-                    seller_fee_basis_points: 0, // Keep current fee
-                    creators: None, // Keep current creators
-                    collection: None, // Keep current collection
-                    uses: None, // Keep current uses
+                    seller_fee_basis_points: new_seller_fee_basis_points
+                        .unwrap_or(current_data.seller_fee_basis_points),
+                    creators: new_creators.or(current_data.creators),
+                    collection: current_metadata.collection,
+                    uses: current_metadata.uses,
                 }),
                 None,
                 None,
@@ -159,9 +383,228 @@ pub mod nft_program {
                 ctx.accounts.authority.to_account_info(),
             ],
         )?;
-        
+
         msg!("Metadata updated for NFT");
-        
+
+        Ok(())
+    }
+
+    /// Mint a new numbered print from an existing master edition
+    pub fn print_edition(
+        ctx: Context<PrintEdition>,
+        edition: u64,
+    ) -> Result<()> {
+        msg!("Printing edition #{}", edition);
+
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.new_mint.to_account_info(),
+            to: ctx.accounts.new_token_account.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::mint_to(cpi_ctx, 1)?;
+
+        invoke(
+            &mpl_instruction::mint_new_edition_from_master_edition_via_token(
+                ctx.accounts.token_metadata_program.key(),
+                ctx.accounts.new_metadata.key(),
+                ctx.accounts.new_edition.key(),
+                ctx.accounts.master_edition.key(),
+                ctx.accounts.new_mint.key(),
+                ctx.accounts.mint_authority.key(),
+                ctx.accounts.payer.key(),
+                ctx.accounts.master_token_account_owner.key(),
+                ctx.accounts.master_token_account.key(),
+                ctx.accounts.mint_authority.key(),
+                ctx.accounts.master_metadata.key(),
+                ctx.accounts.master_mint.key(),
+                edition,
+            ),
+            &[
+                ctx.accounts.new_metadata.to_account_info(),
+                ctx.accounts.new_edition.to_account_info(),
+                ctx.accounts.master_edition.to_account_info(),
+                ctx.accounts.new_mint.to_account_info(),
+                ctx.accounts.mint_authority.to_account_info(),
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.master_token_account_owner.to_account_info(),
+                ctx.accounts.master_token_account.to_account_info(),
+                ctx.accounts.master_metadata.to_account_info(),
+                ctx.accounts.master_mint.to_account_info(),
+                ctx.accounts.edition_marker.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+            ],
+        )?;
+
+        msg!("Edition #{} printed successfully", edition);
+
+        Ok(())
+    }
+
+    /// Create a new NFT on Token-2022, storing its metadata directly on the mint via the
+    /// metadata-pointer + token-metadata extensions instead of a separate Metaplex account
+    pub fn create_nft_2022(
+        ctx: Context<CreateNft2022>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        msg!("Creating new Token-2022 NFT: {}", name);
+
+        // The `extensions::metadata_pointer` constraint on the mint account sized it for
+        // the pointer extension and pointed the mint at itself as its own metadata account;
+        // this CPI writes the actual name/symbol/uri onto the mint, and Token-2022 grows
+        // the mint account to hold them
+        let cpi_accounts = TokenMetadataInitialize {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            metadata: ctx.accounts.mint.to_account_info(),
+            mint_authority: ctx.accounts.authority.to_account_info(),
+            update_authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::token_metadata_initialize(cpi_ctx, name, symbol, uri)?;
+
+        let cpi_accounts = token_interface::MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.token_account.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::mint_to(cpi_ctx, 1)?;
+
+        msg!("Token-2022 NFT created successfully");
+
+        Ok(())
+    }
+
+    /// Burn an NFT, closing its metadata/edition/token accounts and reclaiming their rent
+    /// back to the owner
+    pub fn burn_nft(ctx: Context<BurnNft>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.token_account.owner,
+            ctx.accounts.owner.key(),
+            ErrorCode::NotTokenOwner
+        );
+
+        let mut account_infos = vec![
+            ctx.accounts.metadata.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.master_edition.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+        ];
+
+        let collection_metadata = ctx.accounts.collection_metadata.as_ref().map(|account| {
+            account_infos.push(account.to_account_info());
+            account.key()
+        });
+
+        invoke(
+            &mpl_instruction::burn_nft(
+                ctx.accounts.token_metadata_program.key(),
+                ctx.accounts.metadata.key(),
+                ctx.accounts.owner.key(),
+                ctx.accounts.mint.key(),
+                ctx.accounts.token_account.key(),
+                ctx.accounts.master_edition.key(),
+                ctx.accounts.token_program.key(),
+                collection_metadata,
+            ),
+            &account_infos,
+        )?;
+
+        msg!("NFT burned and rent reclaimed");
+
+        Ok(())
+    }
+
+    /// Transfer an NFT to a buyer for `sale_price`, splitting creator royalties off the
+    /// top and paying the remainder to the seller before moving the NFT
+    pub fn transfer_with_royalties(
+        ctx: Context<TransferWithRoyalties>,
+        sale_price: u64,
+    ) -> Result<()> {
+        let metadata = mpl_token_metadata::state::Metadata::from_account_info(
+            &ctx.accounts.metadata.to_account_info(),
+        )?;
+        let creators = metadata.data.creators.unwrap_or_default();
+
+        require_eq!(
+            creators.len(),
+            ctx.remaining_accounts.len(),
+            ErrorCode::CreatorAccountMismatch
+        );
+
+        for (creator, destination) in creators.iter().zip(ctx.remaining_accounts.iter()) {
+            require_keys_eq!(
+                creator.address,
+                destination.key(),
+                ErrorCode::CreatorAccountMismatch
+            );
+        }
+
+        let (royalty_amount, payouts) = calculate_royalty_payouts(
+            sale_price,
+            metadata.data.seller_fee_basis_points,
+            &creators,
+        )?;
+
+        for (payout, destination) in payouts.iter().zip(ctx.remaining_accounts.iter()) {
+            if *payout > 0 {
+                invoke(
+                    &system_instruction::transfer(ctx.accounts.buyer.key, destination.key, *payout),
+                    &[
+                        ctx.accounts.buyer.to_account_info(),
+                        destination.clone(),
+                        ctx.accounts.system_program.to_account_info(),
+                    ],
+                )?;
+            }
+        }
+
+        // Pay the seller whatever's left of the sale price after creator royalties
+        let seller_proceeds = sale_price
+            .checked_sub(royalty_amount)
+            .ok_or(ErrorCode::RoyaltyOverflow)?;
+        if seller_proceeds > 0 {
+            invoke(
+                &system_instruction::transfer(
+                    ctx.accounts.buyer.key,
+                    ctx.accounts.seller.key,
+                    seller_proceeds,
+                ),
+                &[
+                    ctx.accounts.buyer.to_account_info(),
+                    ctx.accounts.seller.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+            )?;
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::transfer(cpi_ctx, 1)?;
+
+        msg!(
+            "NFT transferred for {} lamports ({} paid out in royalties, {} to seller)",
+            sale_price,
+            royalty_amount,
+            seller_proceeds
+        );
+
         Ok(())
     }
 }
@@ -221,6 +664,93 @@ pub struct CreateNFT<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+pub struct CreateCollection<'info> {
+    /// The collection mint account
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = authority.key(),
+        mint::freeze_authority = authority.key(),
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// The token account that will hold the collection NFT
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// Metadata account for the collection NFT
+    /// CHECK: Created by Metaplex program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Master edition account for the collection NFT
+    /// CHECK: Created by Metaplex program
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+
+    /// Update authority of the collection
+    pub authority: Signer<'info>,
+
+    /// Payer for the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Associated Token program
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+
+    /// Metaplex Token Metadata program
+    /// CHECK: This is the Metaplex program
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyCollection<'info> {
+    /// Metadata account of the NFT being (un)verified into the collection
+    /// CHECK: Verified by Metaplex program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Mint of the collection NFT
+    /// CHECK: Verified by Metaplex program
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// Metadata account of the collection NFT
+    /// CHECK: Verified by Metaplex program
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// Master edition account of the collection NFT
+    /// CHECK: Verified by Metaplex program
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    /// Update authority of the collection NFT
+    pub collection_authority: Signer<'info>,
+
+    /// Payer for the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Metaplex Token Metadata program
+    /// CHECK: This is the Metaplex program
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
+
 #[derive(Accounts)]
 pub struct TransferNFT<'info> {
     /// Source token account
@@ -251,4 +781,261 @@ pub struct UpdateMetadata<'info> {
     /// Metaplex Token Metadata program
     /// CHECK: This is the Metaplex program
     pub token_metadata_program: UncheckedAccount<'info>,
-} 
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+#[instruction(edition: u64)]
+pub struct PrintEdition<'info> {
+    /// Mint for the new printed edition
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = mint_authority.key(),
+        mint::freeze_authority = mint_authority.key(),
+    )]
+    pub new_mint: Account<'info, Mint>,
+
+    /// Token account that will hold the new printed edition
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = new_mint,
+        associated_token::authority = mint_authority,
+    )]
+    pub new_token_account: Account<'info, TokenAccount>,
+
+    /// Metadata account for the new printed edition
+    /// CHECK: Created by Metaplex program
+    #[account(mut)]
+    pub new_metadata: UncheckedAccount<'info>,
+
+    /// Edition account for the new printed edition
+    /// CHECK: Created by Metaplex program
+    #[account(mut)]
+    pub new_edition: UncheckedAccount<'info>,
+
+    /// Mint of the master edition being printed from
+    /// CHECK: Verified by Metaplex program
+    pub master_mint: UncheckedAccount<'info>,
+
+    /// Metadata account of the master edition
+    /// CHECK: Verified by Metaplex program
+    pub master_metadata: UncheckedAccount<'info>,
+
+    /// Master edition account being printed from
+    /// CHECK: Verified by Metaplex program
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+
+    /// Edition marker PDA tracking which editions have been printed, derived
+    /// by Metaplex from the master mint and `edition / EDITION_MARKER_BIT_SIZE`
+    /// CHECK: Created by Metaplex program
+    #[account(mut)]
+    pub edition_marker: UncheckedAccount<'info>,
+
+    /// Token account currently holding the master edition
+    pub master_token_account: Account<'info, TokenAccount>,
+
+    /// Owner of the master edition token account
+    pub master_token_account_owner: Signer<'info>,
+
+    /// Mint/update authority for the new printed edition
+    pub mint_authority: Signer<'info>,
+
+    /// Payer for the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Associated Token program
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+
+    /// Metaplex Token Metadata program
+    /// CHECK: This is the Metaplex program
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CreateNft2022<'info> {
+    /// The NFT mint account, with a metadata-pointer extension pointing at itself
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = authority,
+        mint::freeze_authority = authority,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = authority,
+        extensions::metadata_pointer::metadata_address = mint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint2022>,
+
+    /// The token account that will hold the NFT
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount2022>,
+
+    /// Creator/authority of the NFT
+    pub authority: Signer<'info>,
+
+    /// Payer for the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token-2022 program
+    pub token_program: Program<'info, Token2022>,
+
+    /// Associated Token program
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct BurnNft<'info> {
+    /// Metadata account of the NFT being burned
+    /// CHECK: Verified by Metaplex program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Owner of the NFT; receives the reclaimed rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Mint of the NFT being burned
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    /// Owner's token account holding the NFT
+    #[account(mut)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// Master edition account of the NFT
+    /// CHECK: Verified by Metaplex program
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+
+    /// Metadata account of the collection this NFT is a verified member of, if any
+    /// CHECK: Verified by Metaplex program
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Metaplex Token Metadata program
+    /// CHECK: This is the Metaplex program
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferWithRoyalties<'info> {
+    /// Seller's token account holding the NFT
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+
+    /// Buyer's token account that will receive the NFT
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+
+    /// Metadata account describing the NFT's creators and royalty rate, constrained to the
+    /// PDA Metaplex derives for `from`'s mint so it can't be swapped for an unrelated NFT's
+    /// CHECK: Verified by seeds below
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), from.mint.as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Owner of the NFT being sold; receives the sale proceeds net of royalties
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// Buyer funding the sale price and receiving the NFT
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// System program, used for the creator royalty and seller proceeds payouts
+    pub system_program: Program<'info, System>,
+
+    /// Metaplex Token Metadata program
+    /// CHECK: This is the Metaplex program
+    pub token_metadata_program: UncheckedAccount<'info>,
+    // remaining_accounts: one writable account per entry in the NFT's creator list,
+    // in the same order, each receiving its `share` of the royalty amount
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Signer does not own the NFT's token account")]
+    NotTokenOwner,
+    #[msg("Remaining accounts do not match the NFT's creator list")]
+    CreatorAccountMismatch,
+    #[msg("Creator shares do not sum to 100")]
+    InvalidCreatorShares,
+    #[msg("Royalty amount calculation overflowed")]
+    RoyaltyOverflow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mpl_token_metadata::state::Creator;
+
+    fn creator(share: u8) -> Creator {
+        Creator {
+            address: Pubkey::new_unique(),
+            verified: false,
+            share,
+        }
+    }
+
+    #[test]
+    fn splits_royalty_proportionally_to_shares() {
+        let creators = vec![creator(70), creator(30)];
+
+        let (royalty_amount, payouts) =
+            calculate_royalty_payouts(1_000_000, 500, &creators).unwrap();
+
+        assert_eq!(royalty_amount, 50_000);
+        assert_eq!(payouts, vec![35_000, 15_000]);
+    }
+
+    #[test]
+    fn zero_basis_points_pays_no_royalties() {
+        let creators = vec![creator(100)];
+
+        let (royalty_amount, payouts) = calculate_royalty_payouts(1_000_000, 0, &creators).unwrap();
+
+        assert_eq!(royalty_amount, 0);
+        assert_eq!(payouts, vec![0]);
+    }
+
+    #[test]
+    fn rejects_shares_not_summing_to_100() {
+        let creators = vec![creator(70), creator(20)];
+
+        let result = calculate_royalty_payouts(1_000_000, 500, &creators);
+
+        assert!(result.is_err());
+    }
+}
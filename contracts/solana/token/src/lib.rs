@@ -1,14 +1,68 @@
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
+    program_option::COption,
     pubkey::Pubkey,
     program_pack::{IsInitialized, Pack, Sealed},
     sysvar::{rent::Rent, Sysvar},
 };
 use std::mem::size_of;
+use thiserror::Error;
+
+// 代币程序自定义错误
+#[derive(Error, Debug, Copy, Clone)]
+pub enum TokenError {
+    #[error("account is frozen")]
+    AccountFrozen,
+    #[error("mint has no freeze authority")]
+    NoFreezeAuthority,
+    #[error("mint authority has been permanently disabled")]
+    FixedSupply,
+    #[error("account has no delegate")]
+    NoDelegate,
+    #[error("delegated amount exceeded")]
+    InsufficientDelegatedAmount,
+    #[error("not enough valid signers for multisig authority")]
+    NotEnoughSigners,
+    #[error("multisig m/n out of bounds")]
+    InvalidMultisigConfig,
+}
+
+impl From<TokenError> for ProgramError {
+    fn from(e: TokenError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+// 账户状态:代替原先的`is_initialized`布尔值,额外支持冻结
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccountState {
+    Uninitialized = 0,
+    Initialized = 1,
+    Frozen = 2,
+}
+
+impl Default for AccountState {
+    fn default() -> Self {
+        AccountState::Uninitialized
+    }
+}
+
+impl AccountState {
+    fn from_u8(value: u8) -> Result<Self, ProgramError> {
+        match value {
+            0 => Ok(AccountState::Uninitialized),
+            1 => Ok(AccountState::Initialized),
+            2 => Ok(AccountState::Frozen),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
 
 // 定义代币指令
 #[repr(C)]
@@ -17,6 +71,7 @@ pub enum TokenInstruction {
     InitializeMint {
         decimals: u8,
         mint_authority: Pubkey,
+        freeze_authority: Option<Pubkey>,
     },
     // 铸造代币
     MintTo {
@@ -30,25 +85,144 @@ pub enum TokenInstruction {
     Burn {
         amount: u64,
     },
+    // 冻结账户
+    FreezeAccount,
+    // 解冻账户
+    ThawAccount,
+    // 批准代理人在额度内代为转账/销毁
+    Approve {
+        amount: u64,
+    },
+    // 撤销代理人额度
+    Revoke,
+    // 初始化一个M-of-N多签账户
+    InitializeMultisig {
+        m: u8,
+    },
+}
+
+// 将36字节的`COption<Pubkey>`标签编码写入目标缓冲区:4字节判别式 + 32字节公钥
+fn pack_coption_key(src: &COption<Pubkey>, dst: &mut [u8; 36]) {
+    let (tag, body) = mut_array_refs![dst, 4, 32];
+    match src {
+        COption::Some(key) => {
+            *tag = [1, 0, 0, 0];
+            body.copy_from_slice(key.as_ref());
+        }
+        COption::None => {
+            *tag = [0; 4];
+        }
+    }
+}
+
+// 解析36字节的`COption<Pubkey>`标签编码
+fn unpack_coption_key(src: &[u8; 36]) -> Result<COption<Pubkey>, ProgramError> {
+    let (tag, body) = array_refs![src, 4, 32];
+    match *tag {
+        [0, 0, 0, 0] => Ok(COption::None),
+        [1, 0, 0, 0] => Ok(COption::Some(Pubkey::new_from_array(*body))),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
 }
 
 // 定义代币账户状态
-#[repr(C)]
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct TokenAccount {
-    pub is_initialized: bool,
+    pub state: AccountState,
     pub owner: Pubkey,
     pub amount: u64,
+    pub delegate: COption<Pubkey>,
+    pub delegated_amount: u64,
 }
 
-// 定义代币铸造账户状态
+impl Default for TokenAccount {
+    fn default() -> Self {
+        TokenAccount {
+            state: AccountState::default(),
+            owner: Pubkey::default(),
+            amount: 0,
+            delegate: COption::None,
+            delegated_amount: 0,
+        }
+    }
+}
+
+// 多签账户最多支持的签名者数量
+pub const MAX_SIGNERS: usize = 11;
+
+// M-of-N多签账户:当铸造(`mint_to`)、转账(`transfer`)、销毁(`burn`)所校验的权限指向一个由本程序拥有的多签账户时,
+// 需要至少`m`个`signers[..n]`中的不同签名者联合签名才能通过权限校验;
+// 冻结/解冻、批准/撤销代理人只接受权限账户本身的签名,多签账户无法授权这些操作
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
-pub struct Mint {
+pub struct Multisig {
+    pub m: u8,
+    pub n: u8,
     pub is_initialized: bool,
-    pub mint_authority: Pubkey,
+    pub signers: [Pubkey; MAX_SIGNERS],
+}
+
+impl Sealed for Multisig {}
+
+impl IsInitialized for Multisig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Multisig {
+    const LEN: usize = size_of::<Multisig>();
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut signers = [Pubkey::default(); MAX_SIGNERS];
+        for (i, signer) in signers.iter_mut().enumerate() {
+            let offset = 3 + i * 32;
+            *signer = Pubkey::new(&src[offset..offset + 32]);
+        }
+
+        Ok(Multisig {
+            m: src[0],
+            n: src[1],
+            is_initialized: src[2] != 0,
+            signers,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = self.m;
+        dst[1] = self.n;
+        dst[2] = self.is_initialized as u8;
+        for (i, signer) in self.signers.iter().enumerate() {
+            let offset = 3 + i * 32;
+            dst[offset..offset + 32].copy_from_slice(signer.as_ref());
+        }
+    }
+}
+
+// 定义代币铸造账户状态
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mint {
+    pub mint_authority: COption<Pubkey>,
     pub supply: u64,
     pub decimals: u8,
+    pub is_initialized: bool,
+    pub freeze_authority: COption<Pubkey>,
+}
+
+impl Default for Mint {
+    fn default() -> Self {
+        Mint {
+            mint_authority: COption::None,
+            supply: 0,
+            decimals: 0,
+            is_initialized: false,
+            freeze_authority: COption::None,
+        }
+    }
 }
 
 impl Sealed for TokenAccount {}
@@ -56,7 +230,7 @@ impl Sealed for Mint {}
 
 impl IsInitialized for TokenAccount {
     fn is_initialized(&self) -> bool {
-        self.is_initialized
+        self.state != AccountState::Uninitialized
     }
 }
 
@@ -67,48 +241,76 @@ impl IsInitialized for Mint {
 }
 
 impl Pack for TokenAccount {
-    const LEN: usize = size_of::<TokenAccount>();
-    
+    // 1 (state) + 32 (owner) + 8 (amount) + 36 (delegate COption) + 8 (delegated_amount)
+    const LEN: usize = 85;
+
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let account = TokenAccount {
-            is_initialized: src[0] != 0,
-            owner: Pubkey::new(&src[1..33]),
-            amount: u64::from_le_bytes([
-                src[33], src[34], src[35], src[36], 
-                src[37], src[38], src[39], src[40],
-            ]),
-        };
-        Ok(account)
+        if src.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![src, 0, TokenAccount::LEN];
+        let (state, owner, amount, delegate, delegated_amount) =
+            array_refs![src, 1, 32, 8, 36, 8];
+
+        Ok(TokenAccount {
+            state: AccountState::from_u8(state[0])?,
+            owner: Pubkey::new_from_array(*owner),
+            amount: u64::from_le_bytes(*amount),
+            delegate: unpack_coption_key(delegate)?,
+            delegated_amount: u64::from_le_bytes(*delegated_amount),
+        })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        dst[0] = self.is_initialized as u8;
-        dst[1..33].copy_from_slice(self.owner.as_ref());
-        dst[33..41].copy_from_slice(&self.amount.to_le_bytes());
+        let dst = array_mut_ref![dst, 0, TokenAccount::LEN];
+        let (state_dst, owner_dst, amount_dst, delegate_dst, delegated_amount_dst) =
+            mut_array_refs![dst, 1, 32, 8, 36, 8];
+
+        state_dst[0] = self.state as u8;
+        owner_dst.copy_from_slice(self.owner.as_ref());
+        *amount_dst = self.amount.to_le_bytes();
+        pack_coption_key(&self.delegate, delegate_dst);
+        *delegated_amount_dst = self.delegated_amount.to_le_bytes();
     }
 }
 
 impl Pack for Mint {
-    const LEN: usize = size_of::<Mint>();
-    
+    // 36 (mint_authority COption) + 8 (supply) + 1 (decimals) + 1 (is_initialized) + 36 (freeze_authority COption)
+    const LEN: usize = 82;
+
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let mint = Mint {
-            is_initialized: src[0] != 0,
-            mint_authority: Pubkey::new(&src[1..33]),
-            supply: u64::from_le_bytes([
-                src[33], src[34], src[35], src[36], 
-                src[37], src[38], src[39], src[40],
-            ]),
-            decimals: src[41],
+        if src.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![src, 0, Mint::LEN];
+        let (mint_authority, supply, decimals, is_initialized, freeze_authority) =
+            array_refs![src, 36, 8, 1, 1, 36];
+
+        let is_initialized = match is_initialized {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidAccountData),
         };
-        Ok(mint)
+
+        Ok(Mint {
+            mint_authority: unpack_coption_key(mint_authority)?,
+            supply: u64::from_le_bytes(*supply),
+            decimals: decimals[0],
+            is_initialized,
+            freeze_authority: unpack_coption_key(freeze_authority)?,
+        })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        dst[0] = self.is_initialized as u8;
-        dst[1..33].copy_from_slice(self.mint_authority.as_ref());
-        dst[33..41].copy_from_slice(&self.supply.to_le_bytes());
-        dst[41] = self.decimals;
+        let dst = array_mut_ref![dst, 0, Mint::LEN];
+        let (mint_authority_dst, supply_dst, decimals_dst, is_initialized_dst, freeze_authority_dst) =
+            mut_array_refs![dst, 36, 8, 1, 1, 36];
+
+        pack_coption_key(&self.mint_authority, mint_authority_dst);
+        *supply_dst = self.supply.to_le_bytes();
+        decimals_dst[0] = self.decimals;
+        is_initialized_dst[0] = self.is_initialized as u8;
+        pack_coption_key(&self.freeze_authority, freeze_authority_dst);
     }
 }
 
@@ -132,9 +334,18 @@ pub fn process_instruction(
             }
             let decimals = instruction_data[1];
             let mint_authority = Pubkey::new(&instruction_data[2..34]);
+            let freeze_authority = if instruction_data.len() >= 35 && instruction_data[34] != 0 {
+                if instruction_data.len() < 67 {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                Some(Pubkey::new(&instruction_data[35..67]))
+            } else {
+                None
+            };
             TokenInstruction::InitializeMint {
                 decimals,
                 mint_authority,
+                freeze_authority,
             }
         },
         1 => {
@@ -167,13 +378,32 @@ pub fn process_instruction(
             ]);
             TokenInstruction::Burn { amount }
         },
+        4 => TokenInstruction::FreezeAccount,
+        5 => TokenInstruction::ThawAccount,
+        6 => {
+            if instruction_data.len() < 9 {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let amount = u64::from_le_bytes([
+                instruction_data[1], instruction_data[2], instruction_data[3], instruction_data[4],
+                instruction_data[5], instruction_data[6], instruction_data[7], instruction_data[8],
+            ]);
+            TokenInstruction::Approve { amount }
+        },
+        7 => TokenInstruction::Revoke,
+        8 => {
+            if instruction_data.len() < 2 {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            TokenInstruction::InitializeMultisig { m: instruction_data[1] }
+        },
         _ => return Err(ProgramError::InvalidInstructionData),
     };
 
     match instruction {
-        TokenInstruction::InitializeMint { decimals, mint_authority } => {
+        TokenInstruction::InitializeMint { decimals, mint_authority, freeze_authority } => {
             msg!("Instruction: InitializeMint");
-            process_initialize_mint(accounts, decimals, mint_authority, program_id)
+            process_initialize_mint(accounts, decimals, mint_authority, freeze_authority, program_id)
         },
         TokenInstruction::MintTo { amount } => {
             msg!("Instruction: MintTo");
@@ -187,6 +417,26 @@ pub fn process_instruction(
             msg!("Instruction: Burn");
             process_burn(accounts, amount, program_id)
         },
+        TokenInstruction::FreezeAccount => {
+            msg!("Instruction: FreezeAccount");
+            process_toggle_freeze(accounts, program_id, true)
+        },
+        TokenInstruction::ThawAccount => {
+            msg!("Instruction: ThawAccount");
+            process_toggle_freeze(accounts, program_id, false)
+        },
+        TokenInstruction::Approve { amount } => {
+            msg!("Instruction: Approve");
+            process_approve(accounts, amount, program_id)
+        },
+        TokenInstruction::Revoke => {
+            msg!("Instruction: Revoke");
+            process_revoke(accounts, program_id)
+        },
+        TokenInstruction::InitializeMultisig { m } => {
+            msg!("Instruction: InitializeMultisig");
+            process_initialize_multisig(accounts, m, program_id)
+        },
     }
 }
 
@@ -195,6 +445,7 @@ fn process_initialize_mint(
     accounts: &[AccountInfo],
     decimals: u8,
     mint_authority: Pubkey,
+    freeze_authority: Option<Pubkey>,
     program_id: &Pubkey,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
@@ -217,15 +468,105 @@ fn process_initialize_mint(
     }
 
     mint.is_initialized = true;
-    mint.mint_authority = mint_authority;
+    mint.mint_authority = COption::Some(mint_authority);
+    mint.freeze_authority = match freeze_authority {
+        Some(freeze_authority) => COption::Some(freeze_authority),
+        None => COption::None,
+    };
     mint.supply = 0;
     mint.decimals = decimals;
-    
+
     Mint::pack(mint, &mut mint_data)?;
 
     Ok(())
 }
 
+// 初始化一个M-of-N多签账户,签名者取自传入的剩余账户
+fn process_initialize_multisig(
+    accounts: &[AccountInfo],
+    m: u8,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let multisig_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let signer_infos: Vec<&AccountInfo> = account_info_iter.collect();
+
+    if multisig_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let rent = &Rent::from_account_info(rent_info)?;
+    if !rent.is_exempt(multisig_info.lamports(), multisig_info.data_len()) {
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+
+    let n = signer_infos.len();
+    if n == 0 || n > MAX_SIGNERS || m == 0 || (m as usize) > n {
+        return Err(TokenError::InvalidMultisigConfig.into());
+    }
+
+    let mut multisig_data = multisig_info.data.borrow_mut();
+    let mut multisig = Multisig::unpack_unchecked(&multisig_data)?;
+    if multisig.is_initialized {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let mut signers = [Pubkey::default(); MAX_SIGNERS];
+    for (slot, signer_info) in signers.iter_mut().zip(signer_infos.iter()) {
+        *slot = *signer_info.key;
+    }
+
+    multisig.m = m;
+    multisig.n = n as u8;
+    multisig.is_initialized = true;
+    multisig.signers = signers;
+
+    Multisig::pack(multisig, &mut multisig_data)?;
+
+    Ok(())
+}
+
+// 校验`authority_info`是否有权代表`expected_authority`:要么`authority_info`本身就是
+// `expected_authority`并完成了签名,要么`expected_authority`是一个由本程序拥有的多签账户,
+// 此时从`other_accounts`中统计出至少`m`个该多签账户签名者集合内的不同签名者
+fn validate_owner(
+    program_id: &Pubkey,
+    expected_authority: &Pubkey,
+    authority_info: &AccountInfo,
+    other_accounts: &[AccountInfo],
+) -> ProgramResult {
+    if authority_info.key != expected_authority {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if authority_info.owner != program_id {
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        return Ok(());
+    }
+
+    let multisig_data = authority_info.data.borrow();
+    let multisig = Multisig::unpack(&multisig_data)?;
+
+    let mut matched_signers: Vec<&Pubkey> = Vec::new();
+    for signer_info in other_accounts {
+        if !signer_info.is_signer || matched_signers.contains(&signer_info.key) {
+            continue;
+        }
+        if multisig.signers[..multisig.n as usize].contains(signer_info.key) {
+            matched_signers.push(signer_info.key);
+        }
+    }
+
+    if (matched_signers.len() as u8) < multisig.m {
+        return Err(TokenError::NotEnoughSigners.into());
+    }
+
+    Ok(())
+}
+
 // 铸造代币
 fn process_mint_to(
     accounts: &[AccountInfo],
@@ -236,6 +577,7 @@ fn process_mint_to(
     let mint_info = next_account_info(account_info_iter)?;
     let destination_info = next_account_info(account_info_iter)?;
     let authority_info = next_account_info(account_info_iter)?;
+    let signer_infos: Vec<AccountInfo> = account_info_iter.cloned().collect();
 
     if mint_info.owner != program_id || destination_info.owner != program_id {
         return Err(ProgramError::IncorrectProgramId);
@@ -244,13 +586,19 @@ fn process_mint_to(
     let mut mint_data = mint_info.data.borrow_mut();
     let mut mint = Mint::unpack(&mint_data)?;
 
-    if authority_info.key != &mint.mint_authority {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(TokenError::FixedSupply.into()),
+    };
+    validate_owner(program_id, &mint_authority, authority_info, &signer_infos)?;
 
     let mut destination_data = destination_info.data.borrow_mut();
     let mut destination = TokenAccount::unpack(&destination_data)?;
 
+    if destination.state == AccountState::Frozen {
+        return Err(TokenError::AccountFrozen.into());
+    }
+
     mint.supply = mint.supply.checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
     destination.amount = destination.amount.checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
 
@@ -270,6 +618,7 @@ fn process_transfer(
     let source_info = next_account_info(account_info_iter)?;
     let destination_info = next_account_info(account_info_iter)?;
     let authority_info = next_account_info(account_info_iter)?;
+    let signer_infos: Vec<AccountInfo> = account_info_iter.cloned().collect();
 
     if source_info.owner != program_id || destination_info.owner != program_id {
         return Err(ProgramError::IncorrectProgramId);
@@ -278,13 +627,19 @@ fn process_transfer(
     let mut source_data = source_info.data.borrow_mut();
     let mut source = TokenAccount::unpack(&source_data)?;
 
-    if authority_info.key != &source.owner {
-        return Err(ProgramError::InvalidAccountData);
+    let is_delegate = spend_as_delegate(program_id, &source, authority_info, &signer_infos, amount)?;
+
+    if source.state == AccountState::Frozen {
+        return Err(TokenError::AccountFrozen.into());
     }
 
     let mut destination_data = destination_info.data.borrow_mut();
     let mut destination = TokenAccount::unpack(&destination_data)?;
 
+    if destination.state == AccountState::Frozen {
+        return Err(TokenError::AccountFrozen.into());
+    }
+
     if amount > source.amount {
         return Err(ProgramError::InsufficientFunds);
     }
@@ -292,12 +647,49 @@ fn process_transfer(
     source.amount = source.amount.checked_sub(amount).ok_or(ProgramError::InvalidArgument)?;
     destination.amount = destination.amount.checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
 
+    if is_delegate {
+        consume_delegated_amount(&mut source, amount);
+    }
+
     TokenAccount::pack(source, &mut source_data)?;
     TokenAccount::pack(destination, &mut destination_data)?;
 
     Ok(())
 }
 
+// 确认`authority_info`是源账户的所有者(单签或多签)或仍有足够额度的代理人;
+// 返回是否以代理人身份使用
+fn spend_as_delegate(
+    program_id: &Pubkey,
+    source: &TokenAccount,
+    authority_info: &AccountInfo,
+    other_accounts: &[AccountInfo],
+    amount: u64,
+) -> Result<bool, ProgramError> {
+    if validate_owner(program_id, &source.owner, authority_info, other_accounts).is_ok() {
+        return Ok(false);
+    }
+
+    match source.delegate {
+        COption::Some(delegate) => {
+            validate_owner(program_id, &delegate, authority_info, other_accounts)?;
+            if amount > source.delegated_amount {
+                return Err(TokenError::InsufficientDelegatedAmount.into());
+            }
+            Ok(true)
+        }
+        COption::None => Err(TokenError::NoDelegate.into()),
+    }
+}
+
+// 代理人使用额度后更新`delegated_amount`,额度耗尽时清除代理人
+fn consume_delegated_amount(source: &mut TokenAccount, amount: u64) {
+    source.delegated_amount = source.delegated_amount.saturating_sub(amount);
+    if source.delegated_amount == 0 {
+        source.delegate = COption::None;
+    }
+}
+
 // 销毁代币
 fn process_burn(
     accounts: &[AccountInfo],
@@ -308,6 +700,7 @@ fn process_burn(
     let source_info = next_account_info(account_info_iter)?;
     let mint_info = next_account_info(account_info_iter)?;
     let authority_info = next_account_info(account_info_iter)?;
+    let signer_infos: Vec<AccountInfo> = account_info_iter.cloned().collect();
 
     if source_info.owner != program_id || mint_info.owner != program_id {
         return Err(ProgramError::IncorrectProgramId);
@@ -316,8 +709,10 @@ fn process_burn(
     let mut source_data = source_info.data.borrow_mut();
     let mut source = TokenAccount::unpack(&source_data)?;
 
-    if authority_info.key != &source.owner {
-        return Err(ProgramError::InvalidAccountData);
+    let is_delegate = spend_as_delegate(program_id, &source, authority_info, &signer_infos, amount)?;
+
+    if source.state == AccountState::Frozen {
+        return Err(TokenError::AccountFrozen.into());
     }
 
     let mut mint_data = mint_info.data.borrow_mut();
@@ -330,8 +725,238 @@ fn process_burn(
     source.amount = source.amount.checked_sub(amount).ok_or(ProgramError::InvalidArgument)?;
     mint.supply = mint.supply.checked_sub(amount).ok_or(ProgramError::InvalidArgument)?;
 
+    if is_delegate {
+        consume_delegated_amount(&mut source, amount);
+    }
+
     TokenAccount::pack(source, &mut source_data)?;
     Mint::pack(mint, &mut mint_data)?;
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+// 冻结/解冻代币账户,由铸造账户的`freeze_authority`签名授权
+fn process_toggle_freeze(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+    freeze: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let target_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+
+    if target_info.owner != program_id || mint_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mint_data = mint_info.data.borrow();
+    let mint = Mint::unpack(&mint_data)?;
+
+    let freeze_authority = match mint.freeze_authority {
+        COption::Some(freeze_authority) => freeze_authority,
+        COption::None => return Err(TokenError::NoFreezeAuthority.into()),
+    };
+    if authority_info.key != &freeze_authority {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut target_data = target_info.data.borrow_mut();
+    let mut target = TokenAccount::unpack(&target_data)?;
+
+    target.state = if freeze {
+        AccountState::Frozen
+    } else {
+        AccountState::Initialized
+    };
+
+    TokenAccount::pack(target, &mut target_data)?;
+
+    Ok(())
+}
+
+// 所有者批准一个代理人在额度内转账/销毁
+fn process_approve(
+    accounts: &[AccountInfo],
+    amount: u64,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let source_info = next_account_info(account_info_iter)?;
+    let delegate_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+
+    if source_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !owner_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut source_data = source_info.data.borrow_mut();
+    let mut source = TokenAccount::unpack(&source_data)?;
+
+    if owner_info.key != &source.owner {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    source.delegate = COption::Some(*delegate_info.key);
+    source.delegated_amount = amount;
+
+    TokenAccount::pack(source, &mut source_data)?;
+
+    Ok(())
+}
+
+// 所有者撤销当前代理人的额度
+fn process_revoke(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let source_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+
+    if source_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !owner_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut source_data = source_info.data.borrow_mut();
+    let mut source = TokenAccount::unpack(&source_data)?;
+
+    if owner_info.key != &source.owner {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    source.delegate = COption::None;
+    source.delegated_amount = 0;
+
+    TokenAccount::pack(source, &mut source_data)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_info<'a>(
+        key: &'a Pubkey,
+        is_signer: bool,
+        owner: &'a Pubkey,
+        lamports: &'a mut u64,
+        data: &'a mut [u8],
+    ) -> AccountInfo<'a> {
+        AccountInfo::new(key, is_signer, false, lamports, data, owner, false, 0)
+    }
+
+    #[test]
+    fn validate_owner_accepts_signed_single_authority() {
+        let program_id = Pubkey::new_unique();
+        let system_program = solana_program::system_program::id();
+        let authority_key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data: [u8; 0] = [];
+        let authority_info =
+            account_info(&authority_key, true, &system_program, &mut lamports, &mut data);
+
+        assert!(validate_owner(&program_id, &authority_key, &authority_info, &[]).is_ok());
+    }
+
+    #[test]
+    fn validate_owner_rejects_unsigned_single_authority() {
+        let program_id = Pubkey::new_unique();
+        let system_program = solana_program::system_program::id();
+        let authority_key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data: [u8; 0] = [];
+        let authority_info =
+            account_info(&authority_key, false, &system_program, &mut lamports, &mut data);
+
+        assert!(validate_owner(&program_id, &authority_key, &authority_info, &[]).is_err());
+    }
+
+    #[test]
+    fn validate_owner_accepts_enough_multisig_signers() {
+        let program_id = Pubkey::new_unique();
+        let multisig_key = Pubkey::new_unique();
+        let signer_keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+
+        let mut signers = [Pubkey::default(); MAX_SIGNERS];
+        signers[..3].copy_from_slice(&signer_keys);
+        let multisig = Multisig {
+            m: 2,
+            n: 3,
+            is_initialized: true,
+            signers,
+        };
+        let mut multisig_data = vec![0u8; Multisig::LEN];
+        multisig.pack_into_slice(&mut multisig_data);
+        let mut multisig_lamports = 0u64;
+        let multisig_info = account_info(
+            &multisig_key,
+            false,
+            &program_id,
+            &mut multisig_lamports,
+            &mut multisig_data,
+        );
+
+        let mut lamports_a = 0u64;
+        let mut data_a: [u8; 0] = [];
+        let signer_a = account_info(&signer_keys[0], true, &program_id, &mut lamports_a, &mut data_a);
+
+        let mut lamports_b = 0u64;
+        let mut data_b: [u8; 0] = [];
+        let signer_b = account_info(&signer_keys[1], true, &program_id, &mut lamports_b, &mut data_b);
+
+        let other_accounts = [signer_a, signer_b];
+
+        assert!(
+            validate_owner(&program_id, &multisig_key, &multisig_info, &other_accounts).is_ok()
+        );
+    }
+
+    #[test]
+    fn validate_owner_rejects_not_enough_multisig_signers() {
+        let program_id = Pubkey::new_unique();
+        let multisig_key = Pubkey::new_unique();
+        let signer_keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+
+        let mut signers = [Pubkey::default(); MAX_SIGNERS];
+        signers[..3].copy_from_slice(&signer_keys);
+        let multisig = Multisig {
+            m: 2,
+            n: 3,
+            is_initialized: true,
+            signers,
+        };
+        let mut multisig_data = vec![0u8; Multisig::LEN];
+        multisig.pack_into_slice(&mut multisig_data);
+        let mut multisig_lamports = 0u64;
+        let multisig_info = account_info(
+            &multisig_key,
+            false,
+            &program_id,
+            &mut multisig_lamports,
+            &mut multisig_data,
+        );
+
+        let mut lamports_a = 0u64;
+        let mut data_a: [u8; 0] = [];
+        let signer_a = account_info(&signer_keys[0], true, &program_id, &mut lamports_a, &mut data_a);
+
+        let other_accounts = [signer_a];
+
+        let result = validate_owner(&program_id, &multisig_key, &multisig_info, &other_accounts);
+        assert!(matches!(
+            result,
+            Err(ProgramError::Custom(code)) if code == TokenError::NotEnoughSigners as u32
+        ));
+    }
+}